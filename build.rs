@@ -0,0 +1,40 @@
+use std::path::Path;
+use std::process::Command;
+
+/// Compiles every GLSL shader under `src/shaders/` to SPIR-V with `glslc`, dropping the
+/// `.spv` into the crate's `OUT_DIR` so `pipeline::Pipeline` can `include_bytes!` it.
+fn main() {
+    let shaders_dir = Path::new("src/shaders");
+    println!("cargo:rerun-if-changed={}", shaders_dir.display());
+
+    let out_dir = std::env::var("OUT_DIR").expect("OUT_DIR not set");
+
+    let entries = std::fs::read_dir(shaders_dir)
+        .unwrap_or_else(|e| panic!("failed to read {}: {e}", shaders_dir.display()));
+
+    for entry in entries {
+        let path = entry.expect("failed to read shader directory entry").path();
+        let Some(extension) = path.extension().and_then(|ext| ext.to_str()) else {
+            continue;
+        };
+        if !matches!(extension, "vert" | "frag" | "comp" | "glsl") {
+            continue;
+        }
+
+        println!("cargo:rerun-if-changed={}", path.display());
+
+        let file_name = path.file_name().unwrap().to_str().unwrap();
+        let output_path = Path::new(&out_dir).join(format!("{file_name}.spv"));
+
+        let status = Command::new("glslc")
+            .arg(&path)
+            .arg("-o")
+            .arg(&output_path)
+            .status()
+            .expect("failed to invoke glslc - is the Vulkan SDK on PATH?");
+
+        if !status.success() {
+            panic!("glslc failed to compile {}", path.display());
+        }
+    }
+}
@@ -11,6 +11,10 @@ use winit::{
 mod swapchain;
 use swapchain::Swapchain;
 mod frame;
+mod debug;
+mod pipeline;
+mod particles;
+mod buffer;
 
 pub struct App {
     entry: ash::Entry,
@@ -21,12 +25,46 @@ pub struct App {
     swapchain: Swapchain,
     frames: Vec<frame::Frame>,
     window: winit::window::Window,
+    resized: bool,
+    graphics_queue: vk::Queue,
+    present_queue: vk::Queue,
+    compute_queue: vk::Queue,
+    frame_index: usize,
+    debug: Option<debug::Debug>,
+    pipeline: pipeline::Pipeline,
+    particles: particles::Particles,
+    last_frame_time: std::time::Instant,
+    last_render_fence: vk::Fence,
+    vertex_buffer: vk::Buffer,
+    vertex_memory: vk::DeviceMemory,
+    index_buffer: vk::Buffer,
+    index_memory: vk::DeviceMemory,
+    index_count: u32,
 }
 
 impl App {
     pub const FRAMES_IN_FLIGHT: u32 = 2;
     pub const REQUIRED_EXTENSIONS: [*const i8; 1] = [ash::khr::swapchain::NAME.as_ptr()];
 
+    const TRIANGLE_VERTICES: [buffer::Vertex; 3] = [
+        buffer::Vertex {
+            position: [0.0, -0.5],
+            color: [1.0, 0.0, 0.0],
+            uv: [0.5, 0.0],
+        },
+        buffer::Vertex {
+            position: [0.5, 0.5],
+            color: [0.0, 1.0, 0.0],
+            uv: [1.0, 1.0],
+        },
+        buffer::Vertex {
+            position: [-0.5, 0.5],
+            color: [0.0, 0.0, 1.0],
+            uv: [0.0, 1.0],
+        },
+    ];
+    const TRIANGLE_INDICES: [u32; 3] = [0, 1, 2];
+
     pub fn new(window: winit::window::Window) -> Result<Self> {
         let entry = unsafe { ash::Entry::load() }?;
         let app_info = vk::ApplicationInfo::default().api_version(vk::API_VERSION_1_3);
@@ -36,17 +74,38 @@ impl App {
                 .iter()
                 .map(|ext| unsafe { CStr::from_ptr(*ext) })
                 .collect::<Vec<&CStr>>();
-        let names: Vec<*const i8> = extension_names.iter().map(|name| name.as_ptr()).collect();
+        let mut names: Vec<*const i8> = extension_names.iter().map(|name| name.as_ptr()).collect();
+
+        let enable_validation =
+            cfg!(debug_assertions) && Self::validation_layer_supported(&entry)?;
+        let enabled_layers: Vec<*const i8> = if enable_validation {
+            vec![debug::VALIDATION_LAYER_NAME.as_ptr()]
+        } else {
+            Vec::new()
+        };
+        if enable_validation {
+            names.push(ash::ext::debug_utils::NAME.as_ptr());
+        }
 
-        let instance_info = vk::InstanceCreateInfo {
+        let debug_info = debug::messenger_create_info();
+        let mut instance_info = vk::InstanceCreateInfo {
             p_application_info: &app_info,
             enabled_extension_count: names.len() as u32,
             pp_enabled_extension_names: names.as_ptr(),
+            enabled_layer_count: enabled_layers.len() as u32,
+            pp_enabled_layer_names: enabled_layers.as_ptr(),
             ..Default::default()
         };
+        if enable_validation {
+            instance_info.p_next = &debug_info as *const _ as *const std::ffi::c_void;
+        }
         let instance = unsafe { entry.create_instance(&instance_info, None) }?;
 
-        let physical_device = Self::pick_physical_device(&instance)?;
+        let debug = if enable_validation {
+            Some(debug::Debug::new(&entry, &instance)?)
+        } else {
+            None
+        };
 
         let surface = Surface {
             instance: ash::khr::surface::Instance::new(&entry, &instance),
@@ -61,44 +120,78 @@ impl App {
             }?,
         };
 
+        let selection = Self::pick_physical_device(&instance, surface.surface, &surface.instance)?;
+        let physical_device = selection.physical_device;
+
         let features = unsafe { instance.get_physical_device_features(physical_device) };
-        let queue_info = [vk::DeviceQueueCreateInfo::default()
-            .queue_priorities(&[1.0])
-            .queue_family_index(
-                Self::find_queue_family_indicies(
-                    &instance,
-                    physical_device,
-                    vk::QueueFlags::GRAPHICS,
-                )
-                .unwrap(),
-            )];
+        let mut vulkan13_features = vk::PhysicalDeviceVulkan13Features::default()
+            .dynamic_rendering(true)
+            .synchronization2(true);
+        let queue_priorities = [1.0];
+        let mut queue_info = vec![vk::DeviceQueueCreateInfo::default()
+            .queue_priorities(&queue_priorities)
+            .queue_family_index(selection.graphics_family)];
+        if selection.compute_family != selection.graphics_family {
+            queue_info.push(
+                vk::DeviceQueueCreateInfo::default()
+                    .queue_priorities(&queue_priorities)
+                    .queue_family_index(selection.compute_family),
+            );
+        }
         let device_info = vk::DeviceCreateInfo::default()
             .enabled_extension_names(&Self::REQUIRED_EXTENSIONS)
             .queue_create_infos(&queue_info)
-            .enabled_features(&features);
+            .enabled_features(&features)
+            .push_next(&mut vulkan13_features);
         let device = unsafe { instance.create_device(physical_device, &device_info, None) }?;
+        let graphics_queue = unsafe { device.get_device_queue(selection.graphics_family, 0) };
+        let present_queue = unsafe { device.get_device_queue(selection.present_family, 0) };
+        let compute_queue = unsafe { device.get_device_queue(selection.compute_family, 0) };
+        let inner_size = window.inner_size();
         let swapchain = Swapchain::new(
             &instance,
             &device,
             physical_device,
             surface.surface,
             &surface.instance,
+            inner_size.width,
+            inner_size.height,
         )?;
         let mut frames = Vec::with_capacity(Self::FRAMES_IN_FLIGHT as usize);
         (0..Self::FRAMES_IN_FLIGHT).for_each(|_| {
-            frames.push(
-                frame::Frame::new(
-                    &device,
-                    Self::find_queue_family_indicies(
-                        &instance,
-                        physical_device,
-                        vk::QueueFlags::GRAPHICS,
-                    )
-                    .unwrap(),
-                )
-                .unwrap(),
-            )
+            frames.push(frame::Frame::new(&device, selection.graphics_family).unwrap())
         });
+        let pipeline = pipeline::Pipeline::new(&device, swapchain.format)?;
+
+        let (vertex_buffer, vertex_memory) = buffer::upload_buffer(
+            &instance,
+            &device,
+            physical_device,
+            selection.graphics_family,
+            graphics_queue,
+            &Self::TRIANGLE_VERTICES,
+            vk::BufferUsageFlags::VERTEX_BUFFER,
+        )?;
+        let (index_buffer, index_memory) = buffer::upload_buffer(
+            &instance,
+            &device,
+            physical_device,
+            selection.graphics_family,
+            graphics_queue,
+            &Self::TRIANGLE_INDICES,
+            vk::BufferUsageFlags::INDEX_BUFFER,
+        )?;
+
+        let particles = particles::Particles::new(
+            &instance,
+            &device,
+            physical_device,
+            graphics_queue,
+            selection.graphics_family,
+            compute_queue,
+            selection.compute_family,
+            swapchain.format,
+        )?;
 
         Ok(Self {
             entry,
@@ -109,33 +202,432 @@ impl App {
             swapchain,
             frames,
             physical_device,
+            resized: false,
+            graphics_queue,
+            present_queue,
+            compute_queue,
+            frame_index: 0,
+            debug,
+            pipeline,
+            particles,
+            last_frame_time: std::time::Instant::now(),
+            last_render_fence: vk::Fence::null(),
+            vertex_buffer,
+            vertex_memory,
+            index_buffer,
+            index_memory,
+            index_count: Self::TRIANGLE_INDICES.len() as u32,
         })
     }
-    fn pick_physical_device(instance: &ash::Instance) -> Result<vk::PhysicalDevice> {
+
+    fn validation_layer_supported(entry: &ash::Entry) -> Result<bool> {
+        let layers = unsafe { entry.enumerate_instance_layer_properties() }?;
+        Ok(layers.iter().any(|layer| {
+            let name = unsafe { CStr::from_ptr(layer.layer_name.as_ptr()) };
+            name == debug::VALIDATION_LAYER_NAME
+        }))
+    }
+
+    /// Waits for the device to go idle, then rebuilds the swapchain against the window's
+    /// current inner size. Called whenever a resize is observed or the swapchain reports
+    /// itself suboptimal/out-of-date.
+    fn recreate_swapchain(&mut self) -> Result<()> {
+        unsafe { self.device.device_wait_idle() }?;
+
+        let inner_size = self.window.inner_size();
+        self.swapchain.recreate(
+            &self.device,
+            self.physical_device,
+            self.surface.surface,
+            &self.surface.instance,
+            inner_size.width,
+            inner_size.height,
+        )?;
+        self.resized = false;
+
+        Ok(())
+    }
+    /// Renders and presents a single frame, round-robining over `Self::FRAMES_IN_FLIGHT`
+    /// frames in flight. Mirrors the `record_submit_commandbuffer` pattern from the ash
+    /// examples: wait on the frame's fence before touching its resources again.
+    fn draw_frame(&mut self) -> Result<()> {
+        let now = std::time::Instant::now();
+        let delta_time = (now - self.last_frame_time).as_secs_f32();
+        self.last_frame_time = now;
+
+        let frame_count = self.frames.len();
+        let frame = &self.frames[self.frame_index];
+
+        unsafe {
+            self.device
+                .wait_for_fences(&[frame.render_fence], true, u64::MAX)?;
+        }
+
+        // The particle buffer isn't double-buffered per frame in flight, so the compute
+        // dispatch below must not start writing it until the last graphics draw that read it
+        // as a vertex buffer has finished — `particles.fence` alone only guards the dispatch's
+        // own previous submission, not that downstream read.
+        if self.last_render_fence != vk::Fence::null() {
+            unsafe {
+                self.device
+                    .wait_for_fences(&[self.last_render_fence], true, u64::MAX)?;
+            }
+        }
+        self.particles.dispatch(&self.device, self.compute_queue, delta_time)?;
+
+        let image_index = match unsafe {
+            self.swapchain.device.acquire_next_image(
+                self.swapchain.swapchain,
+                u64::MAX,
+                frame.swapchain_sem,
+                vk::Fence::null(),
+            )
+        } {
+            Ok((image_index, suboptimal)) => {
+                self.swapchain.suboptimal |= suboptimal;
+                image_index
+            }
+            Err(vk::Result::ERROR_OUT_OF_DATE_KHR) => {
+                self.swapchain.suboptimal = true;
+                return Ok(());
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        unsafe {
+            self.device.reset_fences(&[frame.render_fence])?;
+        }
+
+        let image = self.swapchain.images[image_index as usize];
+        unsafe {
+            self.device.reset_command_buffer(
+                frame.main_command_buffer,
+                vk::CommandBufferResetFlags::empty(),
+            )?;
+            let begin_info = vk::CommandBufferBeginInfo {
+                flags: vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT,
+                ..Default::default()
+            };
+            self.device
+                .begin_command_buffer(frame.main_command_buffer, &begin_info)?;
+
+            Self::transition_image_layout(
+                &self.device,
+                frame.main_command_buffer,
+                image,
+                vk::ImageLayout::UNDEFINED,
+                vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+            );
+
+            let color_attachment = vk::RenderingAttachmentInfo::default()
+                .image_view(self.swapchain.image_views[image_index as usize])
+                .image_layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+                .load_op(vk::AttachmentLoadOp::CLEAR)
+                .store_op(vk::AttachmentStoreOp::STORE)
+                .clear_value(vk::ClearValue {
+                    color: vk::ClearColorValue {
+                        float32: [0.0, 0.0, 0.05, 1.0],
+                    },
+                });
+            let color_attachments = [color_attachment];
+            let rendering_info = vk::RenderingInfo::default()
+                .render_area(vk::Rect2D {
+                    offset: vk::Offset2D { x: 0, y: 0 },
+                    extent: self.swapchain.extent,
+                })
+                .layer_count(1)
+                .color_attachments(&color_attachments);
+
+            self.device
+                .cmd_begin_rendering(frame.main_command_buffer, &rendering_info);
+
+            let viewport = vk::Viewport {
+                x: 0.0,
+                y: 0.0,
+                width: self.swapchain.extent.width as f32,
+                height: self.swapchain.extent.height as f32,
+                min_depth: 0.0,
+                max_depth: 1.0,
+            };
+            let scissor = vk::Rect2D {
+                offset: vk::Offset2D { x: 0, y: 0 },
+                extent: self.swapchain.extent,
+            };
+            self.device
+                .cmd_set_viewport(frame.main_command_buffer, 0, &[viewport]);
+            self.device
+                .cmd_set_scissor(frame.main_command_buffer, 0, &[scissor]);
+            self.device.cmd_bind_pipeline(
+                frame.main_command_buffer,
+                vk::PipelineBindPoint::GRAPHICS,
+                self.pipeline.pipeline,
+            );
+            self.device
+                .cmd_bind_vertex_buffers(frame.main_command_buffer, 0, &[self.vertex_buffer], &[0]);
+            self.device.cmd_bind_index_buffer(
+                frame.main_command_buffer,
+                self.index_buffer,
+                0,
+                vk::IndexType::UINT32,
+            );
+            self.device
+                .cmd_draw_indexed(frame.main_command_buffer, self.index_count, 1, 0, 0, 0);
+
+            self.device.cmd_bind_pipeline(
+                frame.main_command_buffer,
+                vk::PipelineBindPoint::GRAPHICS,
+                self.particles.graphics_pipeline,
+            );
+            self.device
+                .cmd_bind_vertex_buffers(frame.main_command_buffer, 0, &[self.particles.buffer], &[0]);
+            self.device
+                .cmd_draw(frame.main_command_buffer, particles::PARTICLE_COUNT, 1, 0, 0);
+
+            self.device.cmd_end_rendering(frame.main_command_buffer);
+
+            Self::transition_image_layout(
+                &self.device,
+                frame.main_command_buffer,
+                image,
+                vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+                vk::ImageLayout::PRESENT_SRC_KHR,
+            );
+
+            self.device.end_command_buffer(frame.main_command_buffer)?;
+        }
+
+        let wait_semaphores = [frame.swapchain_sem, self.particles.semaphore];
+        let wait_stages = [
+            vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT,
+            vk::PipelineStageFlags::VERTEX_INPUT,
+        ];
+        let signal_semaphores = [frame.rendering_sem];
+        let command_buffers = [frame.main_command_buffer];
+        let submit_info = vk::SubmitInfo::default()
+            .wait_semaphores(&wait_semaphores)
+            .wait_dst_stage_mask(&wait_stages)
+            .command_buffers(&command_buffers)
+            .signal_semaphores(&signal_semaphores);
+        unsafe {
+            self.device
+                .queue_submit(self.graphics_queue, &[submit_info], frame.render_fence)?;
+        }
+        self.last_render_fence = frame.render_fence;
+
+        let swapchains = [self.swapchain.swapchain];
+        let image_indices = [image_index];
+        let present_info = vk::PresentInfoKHR::default()
+            .wait_semaphores(&signal_semaphores)
+            .swapchains(&swapchains)
+            .image_indices(&image_indices);
+        match unsafe {
+            self.swapchain
+                .device
+                .queue_present(self.present_queue, &present_info)
+        } {
+            Ok(suboptimal) => self.swapchain.suboptimal |= suboptimal,
+            Err(vk::Result::ERROR_OUT_OF_DATE_KHR) => self.swapchain.suboptimal = true,
+            Err(e) => return Err(e.into()),
+        }
+
+        self.frame_index = (self.frame_index + 1) % frame_count;
+
+        Ok(())
+    }
+
+    fn transition_image_layout(
+        device: &ash::Device,
+        command_buffer: vk::CommandBuffer,
+        image: vk::Image,
+        old_layout: vk::ImageLayout,
+        new_layout: vk::ImageLayout,
+    ) {
+        let (src_access, dst_access, src_stage, dst_stage) = match (old_layout, new_layout) {
+            (vk::ImageLayout::UNDEFINED, vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL) => (
+                vk::AccessFlags::empty(),
+                vk::AccessFlags::COLOR_ATTACHMENT_WRITE,
+                vk::PipelineStageFlags::TOP_OF_PIPE,
+                vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT,
+            ),
+            (vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL, vk::ImageLayout::PRESENT_SRC_KHR) => (
+                vk::AccessFlags::COLOR_ATTACHMENT_WRITE,
+                vk::AccessFlags::empty(),
+                vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT,
+                vk::PipelineStageFlags::BOTTOM_OF_PIPE,
+            ),
+            _ => unreachable!("unsupported layout transition"),
+        };
+
+        let barrier = vk::ImageMemoryBarrier {
+            src_access_mask: src_access,
+            dst_access_mask: dst_access,
+            old_layout,
+            new_layout,
+            src_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
+            dst_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
+            image,
+            subresource_range: vk::ImageSubresourceRange {
+                aspect_mask: vk::ImageAspectFlags::COLOR,
+                base_mip_level: 0,
+                level_count: 1,
+                base_array_layer: 0,
+                layer_count: 1,
+            },
+            ..Default::default()
+        };
+
+        unsafe {
+            device.cmd_pipeline_barrier(
+                command_buffer,
+                src_stage,
+                dst_stage,
+                vk::DependencyFlags::empty(),
+                &[],
+                &[],
+                &[barrier],
+            );
+        }
+    }
+
+    /// Scores every enumerated device and picks the best one that is actually usable:
+    /// supports `REQUIRED_EXTENSIONS`, has a non-empty swapchain format/present-mode list,
+    /// and has a queue family that is both `GRAPHICS`-capable and can present to `surface`.
+    /// Discrete GPUs are preferred over integrated ones.
+    fn pick_physical_device(
+        instance: &ash::Instance,
+        surface: vk::SurfaceKHR,
+        surface_instance: &ash::khr::surface::Instance,
+    ) -> Result<PhysicalDeviceSelection> {
         let devices = unsafe { instance.enumerate_physical_devices() }?;
-        match devices.len() {
-            0 => Err(anyhow!("No GPU???")),
-            _ => Ok(devices[0]),
+        if devices.is_empty() {
+            return Err(anyhow!("No GPU???"));
+        }
+
+        let mut best: Option<(u32, PhysicalDeviceSelection)> = None;
+        for physical_device in devices {
+            let Some(queue_family) =
+                Self::find_graphics_present_family(instance, physical_device, surface, surface_instance)
+            else {
+                continue;
+            };
+
+            if !Self::supports_required_extensions(instance, physical_device)? {
+                continue;
+            }
+
+            if !Self::supports_dynamic_rendering(instance, physical_device) {
+                continue;
+            }
+
+            let swapchain_support =
+                SwapchainSupportDetails::new(physical_device, surface, surface_instance)?;
+            if !swapchain_support.is_adequate() {
+                continue;
+            }
+
+            let properties = unsafe { instance.get_physical_device_properties(physical_device) };
+            let mut score = properties.limits.max_image_dimension2_d;
+            if properties.device_type == vk::PhysicalDeviceType::DISCRETE_GPU {
+                score += 1000;
+            }
+
+            let compute_family =
+                Self::find_compute_family(instance, physical_device).unwrap_or(queue_family);
+
+            let candidate = PhysicalDeviceSelection {
+                physical_device,
+                graphics_family: queue_family,
+                present_family: queue_family,
+                compute_family,
+            };
+            let replace = match &best {
+                Some((best_score, _)) => score > *best_score,
+                None => true,
+            };
+            if replace {
+                best = Some((score, candidate));
+            }
         }
+
+        best.map(|(_, selection)| selection)
+            .ok_or_else(|| anyhow!("No suitable GPU found"))
     }
 
-    fn find_queue_family_indicies(
+    /// A queue family that can both submit graphics work and present `surface` — this
+    /// crate assumes a single family can do both, which holds on every desktop GPU.
+    fn find_graphics_present_family(
         instance: &ash::Instance,
         physical_device: vk::PhysicalDevice,
-        queue_type: vk::QueueFlags,
+        surface: vk::SurfaceKHR,
+        surface_instance: &ash::khr::surface::Instance,
     ) -> Option<u32> {
         let properties =
             unsafe { instance.get_physical_device_queue_family_properties(physical_device) };
 
         for (i, family) in properties.iter().enumerate() {
-            if family.queue_count > 0 && family.queue_flags.contains(queue_type) {
-                return Some(i as u32);
+            let i = i as u32;
+            if family.queue_count == 0 || !family.queue_flags.contains(vk::QueueFlags::GRAPHICS) {
+                continue;
+            }
+
+            let present_support = unsafe {
+                surface_instance.get_physical_device_surface_support(physical_device, i, surface)
+            }
+            .unwrap_or(false);
+            if present_support {
+                return Some(i);
             }
         }
 
         None
     }
 
+    /// Prefers a queue family that supports `COMPUTE` without also supporting `GRAPHICS`
+    /// (a dedicated async-compute family, where present), falling back to any
+    /// `COMPUTE`-capable family so the particle simulation still has somewhere to run.
+    fn find_compute_family(instance: &ash::Instance, physical_device: vk::PhysicalDevice) -> Option<u32> {
+        let properties =
+            unsafe { instance.get_physical_device_queue_family_properties(physical_device) };
+
+        let dedicated = properties.iter().enumerate().find(|(_, family)| {
+            family.queue_count > 0
+                && family.queue_flags.contains(vk::QueueFlags::COMPUTE)
+                && !family.queue_flags.contains(vk::QueueFlags::GRAPHICS)
+        });
+        if let Some((i, _)) = dedicated {
+            return Some(i as u32);
+        }
+
+        properties
+            .iter()
+            .enumerate()
+            .find(|(_, family)| family.queue_count > 0 && family.queue_flags.contains(vk::QueueFlags::COMPUTE))
+            .map(|(i, _)| i as u32)
+    }
+
+    /// `dynamicRendering` is core in 1.3 but still a feature bit that must be explicitly
+    /// enabled before `cmd_begin_rendering`/dynamic-rendering pipelines are valid to use.
+    fn supports_dynamic_rendering(instance: &ash::Instance, physical_device: vk::PhysicalDevice) -> bool {
+        let mut vulkan13_features = vk::PhysicalDeviceVulkan13Features::default();
+        let mut features2 = vk::PhysicalDeviceFeatures2::default().push_next(&mut vulkan13_features);
+        unsafe { instance.get_physical_device_features2(physical_device, &mut features2) };
+        vulkan13_features.dynamic_rendering == vk::TRUE
+    }
+
+    fn supports_required_extensions(
+        instance: &ash::Instance,
+        physical_device: vk::PhysicalDevice,
+    ) -> Result<bool> {
+        let available =
+            unsafe { instance.enumerate_device_extension_properties(physical_device) }?;
+        Ok(Self::REQUIRED_EXTENSIONS.iter().all(|&required| {
+            let required = unsafe { CStr::from_ptr(required) };
+            available
+                .iter()
+                .any(|ext| unsafe { CStr::from_ptr(ext.extension_name.as_ptr()) } == required)
+        }))
+    }
+
     pub fn run(&mut self, event_loop: EventLoop<()>) {
         event_loop
             .run(move |event, control_flow| match event {
@@ -149,7 +641,25 @@ impl App {
                                 control_flow.exit();
                             }
 
-                            WindowEvent::RedrawRequested => self.window.request_redraw(),
+                            WindowEvent::Resized(_) => self.resized = true,
+                            WindowEvent::RedrawRequested => {
+                                let size = self.window.inner_size();
+                                if size.width == 0 || size.height == 0 {
+                                    // Minimized: nothing to present to, wait for a real size.
+                                } else {
+                                    if self.resized || self.swapchain.suboptimal {
+                                        if let Err(e) = self.recreate_swapchain() {
+                                            log::error!("failed to recreate swapchain: {e}");
+                                        }
+                                    }
+
+                                    if let Err(e) = self.draw_frame() {
+                                        log::error!("failed to draw frame: {e}");
+                                    }
+                                }
+
+                                self.window.request_redraw();
+                            }
                             WindowEvent::KeyboardInput {
                                 event:
                                     KeyEvent {
@@ -187,9 +697,20 @@ struct Surface {
     surface: vk::SurfaceKHR,
 }
 
+/// The physical device `App::new` settled on, plus the queue family indices callers need
+/// instead of re-deriving them from `instance`/`physical_device` every time.
+struct PhysicalDeviceSelection {
+    physical_device: vk::PhysicalDevice,
+    graphics_family: u32,
+    present_family: u32,
+    compute_family: u32,
+}
+
 impl Drop for App {
     fn drop(&mut self) {
         unsafe {
+            let _ = self.device.device_wait_idle();
+
             for view in &self.swapchain.image_views {
                 self.device.destroy_image_view(*view, None);
             }
@@ -197,15 +718,47 @@ impl Drop for App {
             for frame in &self.frames {
                 self.device.destroy_semaphore(frame.swapchain_sem, None);
                 self.device.destroy_semaphore(frame.rendering_sem, None);
+                self.device.destroy_fence(frame.render_fence, None);
                 self.device.destroy_command_pool(frame.command_pool, None);
             }
             self.swapchain
                 .device
                 .destroy_swapchain(self.swapchain.swapchain, None);
+            self.device.destroy_pipeline(self.pipeline.pipeline, None);
+            self.device
+                .destroy_pipeline_layout(self.pipeline.layout, None);
+
+            self.device.destroy_semaphore(self.particles.semaphore, None);
+            self.device.destroy_fence(self.particles.fence, None);
+            self.device
+                .destroy_command_pool(self.particles.command_pool, None);
+            self.device
+                .destroy_pipeline(self.particles.graphics_pipeline, None);
+            self.device
+                .destroy_pipeline_layout(self.particles.graphics_layout, None);
+            self.device
+                .destroy_pipeline(self.particles.compute_pipeline, None);
+            self.device
+                .destroy_pipeline_layout(self.particles.compute_layout, None);
+            self.device
+                .destroy_descriptor_pool(self.particles.descriptor_pool, None);
+            self.device
+                .destroy_descriptor_set_layout(self.particles.descriptor_set_layout, None);
+            self.device.destroy_buffer(self.particles.buffer, None);
+            self.device.free_memory(self.particles.memory, None);
+
+            self.device.destroy_buffer(self.vertex_buffer, None);
+            self.device.free_memory(self.vertex_memory, None);
+            self.device.destroy_buffer(self.index_buffer, None);
+            self.device.free_memory(self.index_memory, None);
+
             self.device.destroy_device(None);
             self.surface
                 .instance
                 .destroy_surface(self.surface.surface, None);
+            if let Some(debug) = &self.debug {
+                debug.loader.destroy_debug_utils_messenger(debug.messenger, None);
+            }
             self.instance.destroy_instance(None);
         }
     }
@@ -0,0 +1,197 @@
+use anyhow::{anyhow, Result};
+use ash::vk;
+use std::mem::size_of;
+
+/// A single point of on-screen geometry: clip-space position, a flat vertex color, and a
+/// texture coordinate carried along vulkan-tutorial-style for when texturing lands (unused
+/// by `triangle.frag` today).
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct Vertex {
+    pub position: [f32; 2],
+    pub color: [f32; 3],
+    pub uv: [f32; 2],
+}
+
+impl Vertex {
+    pub fn binding_description() -> vk::VertexInputBindingDescription {
+        vk::VertexInputBindingDescription::default()
+            .binding(0)
+            .stride(size_of::<Self>() as u32)
+            .input_rate(vk::VertexInputRate::VERTEX)
+    }
+
+    pub fn attribute_descriptions() -> [vk::VertexInputAttributeDescription; 3] {
+        [
+            vk::VertexInputAttributeDescription::default()
+                .location(0)
+                .binding(0)
+                .format(vk::Format::R32G32_SFLOAT)
+                .offset(0),
+            vk::VertexInputAttributeDescription::default()
+                .location(1)
+                .binding(0)
+                .format(vk::Format::R32G32B32_SFLOAT)
+                .offset(size_of::<[f32; 2]>() as u32),
+            vk::VertexInputAttributeDescription::default()
+                .location(2)
+                .binding(0)
+                .format(vk::Format::R32G32_SFLOAT)
+                .offset(size_of::<[f32; 2]>() as u32 + size_of::<[f32; 3]>() as u32),
+        ]
+    }
+}
+
+/// Scans `get_physical_device_memory_properties` for a memory type that both appears in
+/// `type_bits` (a buffer or image's `memory_requirements.memory_type_bits`) and supports
+/// every flag in `properties`.
+pub fn find_memorytype_index(
+    instance: &ash::Instance,
+    physical_device: vk::PhysicalDevice,
+    type_bits: u32,
+    properties: vk::MemoryPropertyFlags,
+) -> Option<u32> {
+    let memory_properties = unsafe { instance.get_physical_device_memory_properties(physical_device) };
+    (0..memory_properties.memory_type_count).find(|&i| {
+        let supported = type_bits & (1 << i) != 0;
+        supported
+            && memory_properties.memory_types[i as usize]
+                .property_flags
+                .contains(properties)
+    })
+}
+
+/// Creates a buffer of `size` bytes and backs it with memory matching `properties`, bound at
+/// offset 0. Caller owns the returned handles and must free them.
+///
+/// `queue_families` lists every queue family that will access the buffer. A single family
+/// (or none, meaning "whichever queue the caller happens to use") creates an `EXCLUSIVE`
+/// buffer; two or more distinct families create a `CONCURRENT` one so the buffer can be
+/// written and read from different queues without an explicit ownership transfer.
+pub fn create_buffer(
+    instance: &ash::Instance,
+    device: &ash::Device,
+    physical_device: vk::PhysicalDevice,
+    size: vk::DeviceSize,
+    usage: vk::BufferUsageFlags,
+    properties: vk::MemoryPropertyFlags,
+    queue_families: &[u32],
+) -> Result<(vk::Buffer, vk::DeviceMemory)> {
+    let mut buffer_info = vk::BufferCreateInfo::default().size(size).usage(usage);
+    buffer_info = if queue_families.len() > 1 {
+        buffer_info
+            .sharing_mode(vk::SharingMode::CONCURRENT)
+            .queue_family_indices(queue_families)
+    } else {
+        buffer_info.sharing_mode(vk::SharingMode::EXCLUSIVE)
+    };
+    let buffer = unsafe { device.create_buffer(&buffer_info, None) }?;
+
+    let requirements = unsafe { device.get_buffer_memory_requirements(buffer) };
+    let memory_type_index =
+        find_memorytype_index(instance, physical_device, requirements.memory_type_bits, properties)
+            .ok_or_else(|| anyhow!("no memory type suitable for buffer"))?;
+
+    let allocate_info = vk::MemoryAllocateInfo::default()
+        .allocation_size(requirements.size)
+        .memory_type_index(memory_type_index);
+    let memory = unsafe { device.allocate_memory(&allocate_info, None) }?;
+    unsafe { device.bind_buffer_memory(buffer, memory, 0) }?;
+
+    Ok((buffer, memory))
+}
+
+/// Copies `size` bytes from `src` to `dst` via a one-time command buffer submitted on
+/// `queue` and waited on with a transient fence — the standard small-transfer pattern used
+/// for staging-buffer uploads.
+pub fn copy_buffer(
+    device: &ash::Device,
+    queue_family: u32,
+    queue: vk::Queue,
+    src: vk::Buffer,
+    dst: vk::Buffer,
+    size: vk::DeviceSize,
+) -> Result<()> {
+    let pool_info = vk::CommandPoolCreateInfo {
+        flags: vk::CommandPoolCreateFlags::TRANSIENT,
+        queue_family_index: queue_family,
+        ..Default::default()
+    };
+    let command_pool = unsafe { device.create_command_pool(&pool_info, None) }?;
+
+    let allocate_info = vk::CommandBufferAllocateInfo::default()
+        .command_pool(command_pool)
+        .level(vk::CommandBufferLevel::PRIMARY)
+        .command_buffer_count(1);
+    let command_buffer = unsafe { device.allocate_command_buffers(&allocate_info) }?[0];
+
+    let begin_info = vk::CommandBufferBeginInfo::default().flags(vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT);
+    unsafe {
+        device.begin_command_buffer(command_buffer, &begin_info)?;
+        let region = vk::BufferCopy::default().size(size);
+        device.cmd_copy_buffer(command_buffer, src, dst, &[region]);
+        device.end_command_buffer(command_buffer)?;
+    }
+
+    let command_buffers = [command_buffer];
+    let submit_info = vk::SubmitInfo::default().command_buffers(&command_buffers);
+    let fence = unsafe { device.create_fence(&vk::FenceCreateInfo::default(), None) }?;
+    unsafe {
+        device.queue_submit(queue, &[submit_info], fence)?;
+        device.wait_for_fences(&[fence], true, u64::MAX)?;
+        device.destroy_fence(fence, None);
+        device.destroy_command_pool(command_pool, None);
+    }
+
+    Ok(())
+}
+
+/// Uploads `data` into a fresh `DEVICE_LOCAL` buffer carrying `usage` (plus `TRANSFER_DST`),
+/// via a `HOST_VISIBLE | HOST_COHERENT` staging buffer copied over with [`copy_buffer`]. The
+/// staging buffer is destroyed before returning.
+pub fn upload_buffer<T: Copy>(
+    instance: &ash::Instance,
+    device: &ash::Device,
+    physical_device: vk::PhysicalDevice,
+    queue_family: u32,
+    queue: vk::Queue,
+    data: &[T],
+    usage: vk::BufferUsageFlags,
+) -> Result<(vk::Buffer, vk::DeviceMemory)> {
+    let size = (data.len() * size_of::<T>()) as vk::DeviceSize;
+
+    let (staging_buffer, staging_memory) = create_buffer(
+        instance,
+        device,
+        physical_device,
+        size,
+        vk::BufferUsageFlags::TRANSFER_SRC,
+        vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT,
+        &[],
+    )?;
+
+    unsafe {
+        let mapped = device.map_memory(staging_memory, 0, size, vk::MemoryMapFlags::empty())?;
+        std::ptr::copy_nonoverlapping(data.as_ptr(), mapped.cast::<T>(), data.len());
+        device.unmap_memory(staging_memory);
+    }
+
+    let (buffer, memory) = create_buffer(
+        instance,
+        device,
+        physical_device,
+        size,
+        usage | vk::BufferUsageFlags::TRANSFER_DST,
+        vk::MemoryPropertyFlags::DEVICE_LOCAL,
+        &[],
+    )?;
+
+    copy_buffer(device, queue_family, queue, staging_buffer, buffer, size)?;
+
+    unsafe {
+        device.destroy_buffer(staging_buffer, None);
+        device.free_memory(staging_memory, None);
+    }
+
+    Ok((buffer, memory))
+}
@@ -0,0 +1,59 @@
+use anyhow::Result;
+use ash::vk;
+use std::ffi::{c_void, CStr};
+
+/// Only layer this crate opts into; absent in most release driver installs, which is why
+/// `App::new` probes for it instead of assuming it is there.
+pub const VALIDATION_LAYER_NAME: &CStr =
+    unsafe { CStr::from_bytes_with_nul_unchecked(b"VK_LAYER_KHRONOS_validation\0") };
+
+pub fn messenger_create_info() -> vk::DebugUtilsMessengerCreateInfoEXT<'static> {
+    vk::DebugUtilsMessengerCreateInfoEXT::default()
+        .message_severity(
+            vk::DebugUtilsMessageSeverityFlagsEXT::VERBOSE
+                | vk::DebugUtilsMessageSeverityFlagsEXT::INFO
+                | vk::DebugUtilsMessageSeverityFlagsEXT::WARNING
+                | vk::DebugUtilsMessageSeverityFlagsEXT::ERROR,
+        )
+        .message_type(
+            vk::DebugUtilsMessageTypeFlagsEXT::GENERAL
+                | vk::DebugUtilsMessageTypeFlagsEXT::VALIDATION
+                | vk::DebugUtilsMessageTypeFlagsEXT::PERFORMANCE,
+        )
+        .pfn_user_callback(Some(debug_callback))
+}
+
+/// The debug messenger plus the loader needed to destroy it. Only constructed when the
+/// validation layer is available and `cfg!(debug_assertions)` is set.
+pub struct Debug {
+    pub loader: ash::ext::debug_utils::Instance,
+    pub messenger: vk::DebugUtilsMessengerEXT,
+}
+
+impl Debug {
+    pub fn new(entry: &ash::Entry, instance: &ash::Instance) -> Result<Self> {
+        let loader = ash::ext::debug_utils::Instance::new(entry, instance);
+        let create_info = messenger_create_info();
+        let messenger = unsafe { loader.create_debug_utils_messenger(&create_info, None) }?;
+
+        Ok(Self { loader, messenger })
+    }
+}
+
+unsafe extern "system" fn debug_callback(
+    severity: vk::DebugUtilsMessageSeverityFlagsEXT,
+    _message_type: vk::DebugUtilsMessageTypeFlagsEXT,
+    callback_data: *const vk::DebugUtilsMessengerCallbackDataEXT,
+    _user_data: *mut c_void,
+) -> vk::Bool32 {
+    let message = unsafe { CStr::from_ptr((*callback_data).p_message) }.to_string_lossy();
+
+    match severity {
+        vk::DebugUtilsMessageSeverityFlagsEXT::ERROR => log::error!("{message}"),
+        vk::DebugUtilsMessageSeverityFlagsEXT::WARNING => log::warn!("{message}"),
+        vk::DebugUtilsMessageSeverityFlagsEXT::INFO => log::info!("{message}"),
+        _ => log::trace!("{message}"),
+    }
+
+    vk::FALSE
+}
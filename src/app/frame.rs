@@ -5,6 +5,9 @@ use ash::vk;
 pub struct Frame {
     pub swapchain_sem: vk::Semaphore,
     pub rendering_sem: vk::Semaphore,
+    /// Signaled at creation so the first `draw_frame` doesn't block waiting on a
+    /// submission that never happened.
+    pub render_fence: vk::Fence,
     pub command_pool: vk::CommandPool,
     pub main_command_buffer: vk::CommandBuffer,
 }
@@ -27,6 +30,7 @@ impl Frame {
         Ok(Frame {
             swapchain_sem: Self::create_sync_objects(device)?,
             rendering_sem: Self::create_sync_objects(device)?,
+            render_fence: Self::create_fence(device)?,
             command_pool,
             main_command_buffer: command_buffer[0],
         })
@@ -36,4 +40,12 @@ impl Frame {
         let info = vk::SemaphoreCreateInfo::default();
         Ok(unsafe { device.create_semaphore(&info, None)? })
     }
+
+    pub fn create_fence(device: &ash::Device) -> Result<vk::Fence> {
+        let info = vk::FenceCreateInfo {
+            flags: vk::FenceCreateFlags::SIGNALED,
+            ..Default::default()
+        };
+        Ok(unsafe { device.create_fence(&info, None)? })
+    }
 }
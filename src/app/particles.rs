@@ -0,0 +1,411 @@
+use super::buffer;
+use anyhow::Result;
+use ash::util::read_spv;
+use ash::vk;
+use rand::Rng;
+use std::ffi::CStr;
+use std::io::Cursor;
+use std::mem::size_of;
+
+pub const PARTICLE_COUNT: u32 = 4096;
+const WORKGROUP_SIZE: u32 = 256;
+
+const ENTRY_POINT: &CStr = unsafe { CStr::from_bytes_with_nul_unchecked(b"main\0") };
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct Particle {
+    position: [f32; 2],
+    velocity: [f32; 2],
+    color: [f32; 4],
+}
+
+/// GPU particle simulation: a device-local storage buffer integrated by a compute shader
+/// each frame on a dedicated compute queue (falling back to the graphics queue where a
+/// device has no separate compute family), then bound as a vertex buffer and drawn as
+/// points on the graphics queue. When the two queues differ, the buffer is created
+/// `CONCURRENT` across both families so the cross-queue write/read pair needs only the
+/// ordinary `semaphore` handoff below, not an explicit queue-family ownership transfer.
+pub struct Particles {
+    pub buffer: vk::Buffer,
+    pub memory: vk::DeviceMemory,
+    pub descriptor_set_layout: vk::DescriptorSetLayout,
+    pub descriptor_pool: vk::DescriptorPool,
+    pub descriptor_set: vk::DescriptorSet,
+    pub compute_layout: vk::PipelineLayout,
+    pub compute_pipeline: vk::Pipeline,
+    pub graphics_layout: vk::PipelineLayout,
+    pub graphics_pipeline: vk::Pipeline,
+    pub command_pool: vk::CommandPool,
+    pub command_buffer: vk::CommandBuffer,
+    pub fence: vk::Fence,
+    pub semaphore: vk::Semaphore,
+}
+
+impl Particles {
+    pub fn new(
+        instance: &ash::Instance,
+        device: &ash::Device,
+        physical_device: vk::PhysicalDevice,
+        graphics_queue: vk::Queue,
+        graphics_family: u32,
+        compute_queue: vk::Queue,
+        compute_family: u32,
+        color_format: vk::Format,
+    ) -> Result<Self> {
+        let buffer_size = (PARTICLE_COUNT as usize * size_of::<Particle>()) as vk::DeviceSize;
+        let shared_families = [compute_family, graphics_family];
+        let buffer_families: &[u32] = if compute_family == graphics_family {
+            &[]
+        } else {
+            &shared_families
+        };
+
+        let (staging_buffer, staging_memory) = buffer::create_buffer(
+            instance,
+            device,
+            physical_device,
+            buffer_size,
+            vk::BufferUsageFlags::TRANSFER_SRC,
+            vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT,
+            &[],
+        )?;
+
+        let seed = Self::seed_particles();
+        unsafe {
+            let data = device.map_memory(staging_memory, 0, buffer_size, vk::MemoryMapFlags::empty())?;
+            std::ptr::copy_nonoverlapping(seed.as_ptr(), data.cast::<Particle>(), seed.len());
+            device.unmap_memory(staging_memory);
+        }
+
+        let (particle_buffer, memory) = buffer::create_buffer(
+            instance,
+            device,
+            physical_device,
+            buffer_size,
+            vk::BufferUsageFlags::TRANSFER_DST
+                | vk::BufferUsageFlags::STORAGE_BUFFER
+                | vk::BufferUsageFlags::VERTEX_BUFFER,
+            vk::MemoryPropertyFlags::DEVICE_LOCAL,
+            buffer_families,
+        )?;
+
+        buffer::copy_buffer(device, graphics_family, graphics_queue, staging_buffer, particle_buffer, buffer_size)?;
+
+        unsafe {
+            device.destroy_buffer(staging_buffer, None);
+            device.free_memory(staging_memory, None);
+        }
+
+        let descriptor_set_layout = Self::create_descriptor_set_layout(device)?;
+        let descriptor_pool = Self::create_descriptor_pool(device)?;
+        let descriptor_set = Self::create_descriptor_set(
+            device,
+            descriptor_pool,
+            descriptor_set_layout,
+            particle_buffer,
+            buffer_size,
+        )?;
+
+        let (compute_layout, compute_pipeline) = Self::create_compute_pipeline(device, descriptor_set_layout)?;
+        let (graphics_layout, graphics_pipeline) = Self::create_graphics_pipeline(device, color_format)?;
+
+        let pool_info = vk::CommandPoolCreateInfo {
+            flags: vk::CommandPoolCreateFlags::RESET_COMMAND_BUFFER,
+            queue_family_index: compute_family,
+            ..Default::default()
+        };
+        let command_pool = unsafe { device.create_command_pool(&pool_info, None) }?;
+        let allocate_info = vk::CommandBufferAllocateInfo {
+            command_pool,
+            level: vk::CommandBufferLevel::PRIMARY,
+            command_buffer_count: 1,
+            ..Default::default()
+        };
+        let command_buffer = unsafe { device.allocate_command_buffers(&allocate_info) }?[0];
+
+        let fence_info = vk::FenceCreateInfo {
+            flags: vk::FenceCreateFlags::SIGNALED,
+            ..Default::default()
+        };
+        let fence = unsafe { device.create_fence(&fence_info, None) }?;
+        let semaphore = unsafe { device.create_semaphore(&vk::SemaphoreCreateInfo::default(), None) }?;
+
+        Ok(Self {
+            buffer: particle_buffer,
+            memory,
+            descriptor_set_layout,
+            descriptor_pool,
+            descriptor_set,
+            compute_layout,
+            compute_pipeline,
+            graphics_layout,
+            graphics_pipeline,
+            command_pool,
+            command_buffer,
+            fence,
+            semaphore,
+        })
+    }
+
+    /// Records and submits the compute dispatch that integrates every particle, signalling
+    /// `semaphore` once the buffer memory barrier guarding the storage writes has been
+    /// recorded. The caller waits on `semaphore` before reading `buffer` as a vertex buffer.
+    pub fn dispatch(&self, device: &ash::Device, queue: vk::Queue, delta_time: f32) -> Result<()> {
+        unsafe {
+            device.wait_for_fences(&[self.fence], true, u64::MAX)?;
+            device.reset_fences(&[self.fence])?;
+            device.reset_command_buffer(self.command_buffer, vk::CommandBufferResetFlags::empty())?;
+
+            let begin_info = vk::CommandBufferBeginInfo {
+                flags: vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT,
+                ..Default::default()
+            };
+            device.begin_command_buffer(self.command_buffer, &begin_info)?;
+
+            device.cmd_bind_pipeline(self.command_buffer, vk::PipelineBindPoint::COMPUTE, self.compute_pipeline);
+            device.cmd_bind_descriptor_sets(
+                self.command_buffer,
+                vk::PipelineBindPoint::COMPUTE,
+                self.compute_layout,
+                0,
+                &[self.descriptor_set],
+                &[],
+            );
+            device.cmd_push_constants(
+                self.command_buffer,
+                self.compute_layout,
+                vk::ShaderStageFlags::COMPUTE,
+                0,
+                &delta_time.to_ne_bytes(),
+            );
+            device.cmd_dispatch(self.command_buffer, PARTICLE_COUNT.div_ceil(WORKGROUP_SIZE), 1, 1);
+
+            let barrier = vk::BufferMemoryBarrier {
+                src_access_mask: vk::AccessFlags::SHADER_WRITE,
+                dst_access_mask: vk::AccessFlags::VERTEX_ATTRIBUTE_READ,
+                src_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
+                dst_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
+                buffer: self.buffer,
+                offset: 0,
+                size: vk::WHOLE_SIZE,
+                ..Default::default()
+            };
+            device.cmd_pipeline_barrier(
+                self.command_buffer,
+                vk::PipelineStageFlags::COMPUTE_SHADER,
+                vk::PipelineStageFlags::VERTEX_INPUT,
+                vk::DependencyFlags::empty(),
+                &[],
+                &[barrier],
+                &[],
+            );
+
+            device.end_command_buffer(self.command_buffer)?;
+
+            let command_buffers = [self.command_buffer];
+            let signal_semaphores = [self.semaphore];
+            let submit_info = vk::SubmitInfo::default()
+                .command_buffers(&command_buffers)
+                .signal_semaphores(&signal_semaphores);
+            device.queue_submit(queue, &[submit_info], self.fence)?;
+        }
+
+        Ok(())
+    }
+
+    fn seed_particles() -> Vec<Particle> {
+        let mut rng = rand::thread_rng();
+        (0..PARTICLE_COUNT)
+            .map(|_| Particle {
+                position: [rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0)],
+                velocity: [rng.gen_range(-0.25..0.25), rng.gen_range(-0.25..0.25)],
+                color: [rng.gen::<f32>(), rng.gen::<f32>(), rng.gen::<f32>(), 1.0],
+            })
+            .collect()
+    }
+
+    fn create_descriptor_set_layout(device: &ash::Device) -> Result<vk::DescriptorSetLayout> {
+        let bindings = [vk::DescriptorSetLayoutBinding::default()
+            .binding(0)
+            .descriptor_type(vk::DescriptorType::STORAGE_BUFFER)
+            .descriptor_count(1)
+            .stage_flags(vk::ShaderStageFlags::COMPUTE)];
+        let info = vk::DescriptorSetLayoutCreateInfo::default().bindings(&bindings);
+        Ok(unsafe { device.create_descriptor_set_layout(&info, None) }?)
+    }
+
+    fn create_descriptor_pool(device: &ash::Device) -> Result<vk::DescriptorPool> {
+        let pool_sizes = [vk::DescriptorPoolSize {
+            ty: vk::DescriptorType::STORAGE_BUFFER,
+            descriptor_count: 1,
+        }];
+        let info = vk::DescriptorPoolCreateInfo::default()
+            .pool_sizes(&pool_sizes)
+            .max_sets(1);
+        Ok(unsafe { device.create_descriptor_pool(&info, None) }?)
+    }
+
+    fn create_descriptor_set(
+        device: &ash::Device,
+        pool: vk::DescriptorPool,
+        layout: vk::DescriptorSetLayout,
+        buffer: vk::Buffer,
+        size: vk::DeviceSize,
+    ) -> Result<vk::DescriptorSet> {
+        let layouts = [layout];
+        let allocate_info = vk::DescriptorSetAllocateInfo::default()
+            .descriptor_pool(pool)
+            .set_layouts(&layouts);
+        let descriptor_set = unsafe { device.allocate_descriptor_sets(&allocate_info) }?[0];
+
+        let buffer_info = [vk::DescriptorBufferInfo::default().buffer(buffer).offset(0).range(size)];
+        let write = vk::WriteDescriptorSet::default()
+            .dst_set(descriptor_set)
+            .dst_binding(0)
+            .descriptor_type(vk::DescriptorType::STORAGE_BUFFER)
+            .buffer_info(&buffer_info);
+        unsafe { device.update_descriptor_sets(&[write], &[]) };
+
+        Ok(descriptor_set)
+    }
+
+    fn create_compute_pipeline(
+        device: &ash::Device,
+        descriptor_set_layout: vk::DescriptorSetLayout,
+    ) -> Result<(vk::PipelineLayout, vk::Pipeline)> {
+        let module = Self::create_shader_module(
+            device,
+            include_bytes!(concat!(env!("OUT_DIR"), "/particles.comp.spv")),
+        )?;
+
+        let set_layouts = [descriptor_set_layout];
+        let push_constant_ranges = [vk::PushConstantRange::default()
+            .stage_flags(vk::ShaderStageFlags::COMPUTE)
+            .offset(0)
+            .size(size_of::<f32>() as u32)];
+        let layout_info = vk::PipelineLayoutCreateInfo::default()
+            .set_layouts(&set_layouts)
+            .push_constant_ranges(&push_constant_ranges);
+        let layout = unsafe { device.create_pipeline_layout(&layout_info, None) }?;
+
+        let stage = vk::PipelineShaderStageCreateInfo::default()
+            .stage(vk::ShaderStageFlags::COMPUTE)
+            .module(module)
+            .name(ENTRY_POINT);
+        let pipeline_info = vk::ComputePipelineCreateInfo::default().stage(stage).layout(layout);
+        let pipeline = unsafe { device.create_compute_pipelines(vk::PipelineCache::null(), &[pipeline_info], None) }
+            .map_err(|(_, e)| e)?[0];
+
+        unsafe { device.destroy_shader_module(module, None) };
+
+        Ok((layout, pipeline))
+    }
+
+    /// Points pipeline that reads `position`/`color` straight out of the particle storage
+    /// buffer bound as a vertex buffer (the `velocity` field is skipped over via offset).
+    fn create_graphics_pipeline(
+        device: &ash::Device,
+        color_format: vk::Format,
+    ) -> Result<(vk::PipelineLayout, vk::Pipeline)> {
+        let vert_module = Self::create_shader_module(
+            device,
+            include_bytes!(concat!(env!("OUT_DIR"), "/particles.vert.spv")),
+        )?;
+        let frag_module = Self::create_shader_module(
+            device,
+            include_bytes!(concat!(env!("OUT_DIR"), "/particles.frag.spv")),
+        )?;
+
+        let stages = [
+            vk::PipelineShaderStageCreateInfo::default()
+                .stage(vk::ShaderStageFlags::VERTEX)
+                .module(vert_module)
+                .name(ENTRY_POINT),
+            vk::PipelineShaderStageCreateInfo::default()
+                .stage(vk::ShaderStageFlags::FRAGMENT)
+                .module(frag_module)
+                .name(ENTRY_POINT),
+        ];
+
+        let bindings = [vk::VertexInputBindingDescription::default()
+            .binding(0)
+            .stride(size_of::<Particle>() as u32)
+            .input_rate(vk::VertexInputRate::VERTEX)];
+        let attributes = [
+            vk::VertexInputAttributeDescription::default()
+                .location(0)
+                .binding(0)
+                .format(vk::Format::R32G32_SFLOAT)
+                .offset(0),
+            vk::VertexInputAttributeDescription::default()
+                .location(1)
+                .binding(0)
+                .format(vk::Format::R32G32B32A32_SFLOAT)
+                .offset(2 * size_of::<[f32; 2]>() as u32),
+        ];
+        let vertex_input_info = vk::PipelineVertexInputStateCreateInfo::default()
+            .vertex_binding_descriptions(&bindings)
+            .vertex_attribute_descriptions(&attributes);
+
+        let input_assembly_info =
+            vk::PipelineInputAssemblyStateCreateInfo::default().topology(vk::PrimitiveTopology::POINT_LIST);
+
+        let viewport_state = vk::PipelineViewportStateCreateInfo::default()
+            .viewport_count(1)
+            .scissor_count(1);
+
+        let rasterization_info = vk::PipelineRasterizationStateCreateInfo::default()
+            .polygon_mode(vk::PolygonMode::FILL)
+            .cull_mode(vk::CullModeFlags::NONE)
+            .front_face(vk::FrontFace::CLOCKWISE)
+            .line_width(1.0);
+
+        let multisample_info =
+            vk::PipelineMultisampleStateCreateInfo::default().rasterization_samples(vk::SampleCountFlags::TYPE_1);
+
+        let color_blend_attachments =
+            [vk::PipelineColorBlendAttachmentState::default().color_write_mask(vk::ColorComponentFlags::RGBA)];
+        let color_blend_info =
+            vk::PipelineColorBlendStateCreateInfo::default().attachments(&color_blend_attachments);
+
+        let dynamic_states = [vk::DynamicState::VIEWPORT, vk::DynamicState::SCISSOR];
+        let dynamic_state_info =
+            vk::PipelineDynamicStateCreateInfo::default().dynamic_states(&dynamic_states);
+
+        let layout_info = vk::PipelineLayoutCreateInfo::default();
+        let layout = unsafe { device.create_pipeline_layout(&layout_info, None) }?;
+
+        let color_formats = [color_format];
+        let mut rendering_info =
+            vk::PipelineRenderingCreateInfo::default().color_attachment_formats(&color_formats);
+
+        let pipeline_info = vk::GraphicsPipelineCreateInfo::default()
+            .stages(&stages)
+            .vertex_input_state(&vertex_input_info)
+            .input_assembly_state(&input_assembly_info)
+            .viewport_state(&viewport_state)
+            .rasterization_state(&rasterization_info)
+            .multisample_state(&multisample_info)
+            .color_blend_state(&color_blend_info)
+            .dynamic_state(&dynamic_state_info)
+            .layout(layout)
+            .push_next(&mut rendering_info);
+
+        let pipeline = unsafe { device.create_graphics_pipelines(vk::PipelineCache::null(), &[pipeline_info], None) }
+            .map_err(|(_, e)| e)?[0];
+
+        unsafe {
+            device.destroy_shader_module(vert_module, None);
+            device.destroy_shader_module(frag_module, None);
+        }
+
+        Ok((layout, pipeline))
+    }
+
+    fn create_shader_module(device: &ash::Device, bytes: &[u8]) -> Result<vk::ShaderModule> {
+        let code = read_spv(&mut Cursor::new(bytes))?;
+        let info = vk::ShaderModuleCreateInfo::default().code(&code);
+        Ok(unsafe { device.create_shader_module(&info, None) }?)
+    }
+}
@@ -0,0 +1,109 @@
+use super::buffer::Vertex;
+use anyhow::Result;
+use ash::util::read_spv;
+use ash::vk;
+use std::ffi::CStr;
+use std::io::Cursor;
+
+const ENTRY_POINT: &CStr = unsafe { CStr::from_bytes_with_nul_unchecked(b"main\0") };
+
+/// The triangle graphics pipeline: no `VkRenderPass`/framebuffers since the instance
+/// targets Vulkan 1.3 and renders via `VK_KHR_dynamic_rendering` directly against the
+/// acquired swapchain image view.
+pub struct Pipeline {
+    pub layout: vk::PipelineLayout,
+    pub pipeline: vk::Pipeline,
+}
+
+impl Pipeline {
+    pub fn new(device: &ash::Device, color_format: vk::Format) -> Result<Self> {
+        let vert_module = Self::create_shader_module(
+            device,
+            include_bytes!(concat!(env!("OUT_DIR"), "/triangle.vert.spv")),
+        )?;
+        let frag_module = Self::create_shader_module(
+            device,
+            include_bytes!(concat!(env!("OUT_DIR"), "/triangle.frag.spv")),
+        )?;
+
+        let stages = [
+            vk::PipelineShaderStageCreateInfo::default()
+                .stage(vk::ShaderStageFlags::VERTEX)
+                .module(vert_module)
+                .name(ENTRY_POINT),
+            vk::PipelineShaderStageCreateInfo::default()
+                .stage(vk::ShaderStageFlags::FRAGMENT)
+                .module(frag_module)
+                .name(ENTRY_POINT),
+        ];
+
+        let bindings = [Vertex::binding_description()];
+        let attributes = Vertex::attribute_descriptions();
+        let vertex_input_info = vk::PipelineVertexInputStateCreateInfo::default()
+            .vertex_binding_descriptions(&bindings)
+            .vertex_attribute_descriptions(&attributes);
+        let input_assembly_info = vk::PipelineInputAssemblyStateCreateInfo::default()
+            .topology(vk::PrimitiveTopology::TRIANGLE_LIST);
+
+        // Viewport/scissor are dynamic state, sized from `Swapchain::extent` each frame.
+        let viewport_state = vk::PipelineViewportStateCreateInfo::default()
+            .viewport_count(1)
+            .scissor_count(1);
+
+        let rasterization_info = vk::PipelineRasterizationStateCreateInfo::default()
+            .polygon_mode(vk::PolygonMode::FILL)
+            .cull_mode(vk::CullModeFlags::NONE)
+            .front_face(vk::FrontFace::CLOCKWISE)
+            .line_width(1.0);
+
+        let multisample_info = vk::PipelineMultisampleStateCreateInfo::default()
+            .rasterization_samples(vk::SampleCountFlags::TYPE_1);
+
+        let color_blend_attachments =
+            [vk::PipelineColorBlendAttachmentState::default()
+                .color_write_mask(vk::ColorComponentFlags::RGBA)];
+        let color_blend_info = vk::PipelineColorBlendStateCreateInfo::default()
+            .attachments(&color_blend_attachments);
+
+        let dynamic_states = [vk::DynamicState::VIEWPORT, vk::DynamicState::SCISSOR];
+        let dynamic_state_info =
+            vk::PipelineDynamicStateCreateInfo::default().dynamic_states(&dynamic_states);
+
+        let layout_info = vk::PipelineLayoutCreateInfo::default();
+        let layout = unsafe { device.create_pipeline_layout(&layout_info, None) }?;
+
+        let color_formats = [color_format];
+        let mut rendering_info =
+            vk::PipelineRenderingCreateInfo::default().color_attachment_formats(&color_formats);
+
+        let pipeline_info = vk::GraphicsPipelineCreateInfo::default()
+            .stages(&stages)
+            .vertex_input_state(&vertex_input_info)
+            .input_assembly_state(&input_assembly_info)
+            .viewport_state(&viewport_state)
+            .rasterization_state(&rasterization_info)
+            .multisample_state(&multisample_info)
+            .color_blend_state(&color_blend_info)
+            .dynamic_state(&dynamic_state_info)
+            .layout(layout)
+            .push_next(&mut rendering_info);
+
+        let pipeline = unsafe {
+            device.create_graphics_pipelines(vk::PipelineCache::null(), &[pipeline_info], None)
+        }
+        .map_err(|(_, e)| e)?[0];
+
+        unsafe {
+            device.destroy_shader_module(vert_module, None);
+            device.destroy_shader_module(frag_module, None);
+        }
+
+        Ok(Self { layout, pipeline })
+    }
+
+    fn create_shader_module(device: &ash::Device, bytes: &[u8]) -> Result<vk::ShaderModule> {
+        let code = read_spv(&mut Cursor::new(bytes))?;
+        let info = vk::ShaderModuleCreateInfo::default().code(&code);
+        Ok(unsafe { device.create_shader_module(&info, None) }?)
+    }
+}
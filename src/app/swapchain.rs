@@ -70,14 +70,31 @@ impl SwapchainSupportDetails {
     fn optimal_min_image_count(&self) -> u32 {
         self.caps.min_image_count + 1
     }
+
+    /// Whether this device/surface pair can actually back a swapchain at all.
+    pub fn is_adequate(&self) -> bool {
+        !self.formats.is_empty() && !self.present_modes.is_empty()
+    }
+}
+
+fn preferred_surface_format() -> vk::SurfaceFormatKHR {
+    vk::SurfaceFormatKHR {
+        format: vk::Format::B8G8R8_UNORM,
+        color_space: vk::ColorSpaceKHR::SRGB_NONLINEAR,
+    }
 }
+
 pub struct Swapchain {
     pub device: ash::khr::swapchain::Device,
     pub swapchain: vk::SwapchainKHR,
     pub extent: vk::Extent2D,
     pub images: Vec<vk::Image>,
     pub image_views: Vec<vk::ImageView>,
+    pub format: vk::Format,
     pub details: SwapchainSupportDetails,
+    /// Set when `acquire_next_image`/`queue_present` report `SUBOPTIMAL_KHR` or
+    /// `ERROR_OUT_OF_DATE_KHR`, so the next frame knows to recreate before presenting again.
+    pub suboptimal: bool,
 }
 
 impl Swapchain {
@@ -87,16 +104,15 @@ impl Swapchain {
         physical_device: vk::PhysicalDevice,
         surface: vk::SurfaceKHR,
         surface_instance: &ash::khr::surface::Instance,
+        width: u32,
+        height: u32,
     ) -> Result<Self> {
         let swapchain_device = ash::khr::swapchain::Device::new(instance, device);
         let details = SwapchainSupportDetails::new(physical_device, surface, surface_instance)?;
-        let surface_format = details.choose_surface_format(vk::SurfaceFormatKHR {
-            format: vk::Format::B8G8R8_UNORM,
-            color_space: vk::ColorSpaceKHR::SRGB_NONLINEAR,
-        });
+        let surface_format = details.choose_surface_format(preferred_surface_format());
 
         let present_mode = details.choose_present_mode(vk::PresentModeKHR::MAILBOX);
-        let extent = details.choose_extent(crate::WINDOW_WIDTH, crate::WINDOW_HEIGHT);
+        let extent = details.choose_extent(width, height);
         let min_image_count = details.optimal_min_image_count();
         let swapchain_info = vk::SwapchainCreateInfoKHR {
             surface,
@@ -122,10 +138,63 @@ impl Swapchain {
             extent,
             images,
             image_views,
+            format: surface_format.format,
             details,
+            suboptimal: false,
         })
     }
 
+    /// Rebuilds the swapchain in place against the current window size, reusing the old
+    /// swapchain's resources via `oldSwapchain`. The caller is responsible for calling
+    /// `device_wait_idle` first so the old images/views are no longer in flight.
+    pub fn recreate(
+        &mut self,
+        device: &ash::Device,
+        physical_device: vk::PhysicalDevice,
+        surface: vk::SurfaceKHR,
+        surface_instance: &ash::khr::surface::Instance,
+        width: u32,
+        height: u32,
+    ) -> Result<()> {
+        for view in self.image_views.drain(..) {
+            unsafe { device.destroy_image_view(view, None) };
+        }
+
+        self.details = SwapchainSupportDetails::new(physical_device, surface, surface_instance)?;
+        let surface_format = self.details.choose_surface_format(preferred_surface_format());
+        let present_mode = self.details.choose_present_mode(vk::PresentModeKHR::MAILBOX);
+        let extent = self.details.choose_extent(width, height);
+        let min_image_count = self.details.optimal_min_image_count();
+
+        let swapchain_info = vk::SwapchainCreateInfoKHR {
+            surface,
+            min_image_count,
+            image_format: surface_format.format,
+            image_color_space: surface_format.color_space,
+            image_extent: extent,
+            image_array_layers: 1,
+            image_usage: vk::ImageUsageFlags::COLOR_ATTACHMENT,
+            image_sharing_mode: vk::SharingMode::EXCLUSIVE,
+            pre_transform: self.details.caps.current_transform,
+            composite_alpha: vk::CompositeAlphaFlagsKHR::OPAQUE,
+            present_mode,
+            clipped: vk::TRUE,
+            old_swapchain: self.swapchain,
+            ..Default::default()
+        };
+        let new_swapchain = unsafe { self.device.create_swapchain(&swapchain_info, None) }?;
+        unsafe { self.device.destroy_swapchain(self.swapchain, None) };
+
+        self.swapchain = new_swapchain;
+        self.images = unsafe { self.device.get_swapchain_images(self.swapchain) }?;
+        self.image_views = Self::create_image_views(device, &self.images, surface_format.format);
+        self.extent = extent;
+        self.format = surface_format.format;
+        self.suboptimal = false;
+
+        Ok(())
+    }
+
     fn create_image_views(
         device: &ash::Device,
         images: &[vk::Image],